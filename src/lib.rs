@@ -0,0 +1,8 @@
+//! # revm-inspectors
+//!
+//! Common inspectors for [revm](https://github.com/bluealloy/revm).
+
+#![doc(issue_tracker_base_url = "https://github.com/paradigmxyz/revm-inspectors/issues/")]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+pub mod tracing;
@@ -0,0 +1,84 @@
+//! A tracer that counts call-selector/calldata-size combinations, mirroring geth's
+//! `4byteTracer`.
+//!
+//! See also <https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers#4byte-tracer>
+
+use alloy_primitives::{hex, Address};
+use revm::{
+    interpreter::{CallInputs, CallOutcome},
+    precompile::{PrecompileSpecId, Precompiles},
+    EvmContext, EvmWiring,
+};
+use revm_inspector::Inspector;
+use std::collections::HashMap;
+
+/// A 4-byte function selector.
+pub type Selector = [u8; 4];
+
+/// An inspector that records the function selector and calldata size (excluding the selector
+/// itself) of every call, across all nested frames.
+///
+/// Calls whose target is a precompile active under the current spec, and calls whose input is
+/// shorter than 4 bytes, are not recorded.
+///
+/// This is implemented based on
+/// [geth's 4byteTracer](https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers#4byte-tracer),
+/// which fingerprints which ABI functions and argument sizes a transaction exercises.
+#[derive(Clone, Debug, Default)]
+pub struct FourByteInspector {
+    /// Map of `(selector, calldata size excluding the selector)` to the number of times that
+    /// combination was observed.
+    counts: HashMap<(Selector, usize), u64>,
+}
+
+impl FourByteInspector {
+    /// Returns the recorded selector/calldata-size counts.
+    ///
+    /// Calls whose input is shorter than 4 bytes are not recorded.
+    pub fn counts(&self) -> &HashMap<(Selector, usize), u64> {
+        &self.counts
+    }
+
+    /// Returns the recorded counts formatted the way geth's `4byteTracer` renders its JSON keys,
+    /// i.e. `"0x<hex-selector>-<size>"`.
+    pub fn counts_by_id(&self) -> HashMap<String, u64> {
+        self.counts
+            .iter()
+            .map(|((selector, size), count)| (format!("0x{}-{size}", hex::encode(selector)), *count))
+            .collect()
+    }
+}
+
+impl<EvmWiringT> Inspector<EvmWiringT> for FourByteInspector
+where
+    EvmWiringT: EvmWiring,
+{
+    fn call(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        // Mirror geth's 4byteTracer, which skips precompile targets entirely rather than
+        // fingerprinting them as if they were ABI-dispatching contracts.
+        if is_precompile(context, inputs.target_address) {
+            return None;
+        }
+
+        let input = &inputs.input;
+        if input.len() >= 4 {
+            let mut selector = Selector::default();
+            selector.copy_from_slice(&input[..4]);
+            *self.counts.entry((selector, input.len() - 4)).or_default() += 1;
+        }
+        None
+    }
+}
+
+/// Whether `address` is one of the precompiles active under the context's spec.
+fn is_precompile<EvmWiringT>(context: &mut EvmContext<EvmWiringT>, address: Address) -> bool
+where
+    EvmWiringT: EvmWiring,
+{
+    let spec_id = PrecompileSpecId::from_spec_id(context.spec_id());
+    Precompiles::new(spec_id).addresses().any(|precompile| *precompile == address)
+}
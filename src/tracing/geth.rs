@@ -0,0 +1,201 @@
+//! Emits the recorded trace as geth's `debug_traceTransaction` default struct-logger JSON, so
+//! traces produced by a [`TracingInspector`] are drop-in comparable with existing node
+//! `debug_trace*` endpoints.
+//!
+//! See also <https://geth.ethereum.org/docs/developers/evm-tracing/basic-traces>
+
+use crate::tracing::{
+    types::{CallTraceArena, CallTraceStep, StorageChange},
+    TracingInspector,
+};
+use alloy_primitives::{hex, Address, U256};
+use revm::interpreter::opcode;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Configuration for [`TracingInspector::geth_struct_log_trace`], mirroring geth's
+/// `disableStack`/`disableMemory`/`disableStorage` struct-logger options so callers can trade
+/// verbosity for output size.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GethStructLogConfig {
+    disable_stack: bool,
+    disable_memory: bool,
+    disable_storage: bool,
+}
+
+impl GethStructLogConfig {
+    /// Creates a new config that captures stack, memory and storage, matching geth's defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether to omit the `stack` field from every struct log, equivalent to geth's
+    /// `disableStack`.
+    pub const fn disable_stack(mut self, disable: bool) -> Self {
+        self.disable_stack = disable;
+        self
+    }
+
+    /// Sets whether to omit the `memory` field from every struct log, equivalent to geth's
+    /// `disableMemory`.
+    pub const fn disable_memory(mut self, disable: bool) -> Self {
+        self.disable_memory = disable;
+        self
+    }
+
+    /// Sets whether to omit the `storage` field from every struct log, equivalent to geth's
+    /// `disableStorage`.
+    pub const fn disable_storage(mut self, disable: bool) -> Self {
+        self.disable_storage = disable;
+        self
+    }
+}
+
+/// A single `structLogs` entry, matching geth's struct-logger JSON shape.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GethStructLog {
+    pub pc: u64,
+    pub op: &'static str,
+    pub gas: u64,
+    pub gas_cost: u64,
+    pub depth: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage: Option<BTreeMap<String, String>>,
+}
+
+/// The top-level geth `debug_traceTransaction` default struct-logger output.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GethStructLoggerTrace {
+    pub gas: u64,
+    pub failed: bool,
+    pub return_value: String,
+    pub struct_logs: Vec<GethStructLog>,
+}
+
+/// Hex-encodes a 32-byte word the way geth renders `stack`/`memory` entries, i.e. without a `0x`
+/// prefix and without leading-zero trimming.
+fn hex_word(word: U256) -> String {
+    hex::encode(word.to_be_bytes::<32>())
+}
+
+/// Whether `op` enters a new call frame, i.e. the arena has a child node for it.
+fn enters_child_frame(op: u8) -> bool {
+    matches!(
+        op,
+        opcode::CALL
+            | opcode::CALLCODE
+            | opcode::DELEGATECALL
+            | opcode::STATICCALL
+            | opcode::CREATE
+            | opcode::CREATE2
+    )
+}
+
+impl TracingInspector {
+    /// Builds the geth-compatible `debug_traceTransaction` default struct-logger JSON output from
+    /// the recorded trace of the last executed call.
+    ///
+    /// `gas_used` and `return_value` come from the transaction's [`ExecutionResult`], since the
+    /// trace arena itself doesn't record the overall outcome.
+    ///
+    /// [`ExecutionResult`]: revm::wiring::result::ExecutionResult
+    pub fn geth_struct_log_trace(
+        &self,
+        gas_used: u64,
+        failed: bool,
+        return_value: Vec<u8>,
+        config: GethStructLogConfig,
+    ) -> GethStructLoggerTrace {
+        // geth's struct logger keeps one running storage map per contract address for the whole
+        // trace, not per call frame, so a later call back into the same contract still sees the
+        // writes an earlier call made.
+        let mut storage_by_contract: HashMap<Address, BTreeMap<String, String>> = HashMap::new();
+        let mut struct_logs = Vec::new();
+
+        let arena = self.traces();
+        if !arena.nodes().is_empty() {
+            Self::collect_struct_logs(arena, 0, config, &mut storage_by_contract, &mut struct_logs);
+        }
+
+        GethStructLoggerTrace {
+            gas: gas_used,
+            failed,
+            return_value: hex::encode(return_value),
+            struct_logs,
+        }
+    }
+
+    /// Appends `node_idx`'s struct logs (and recursively, its children's) to `out` in true
+    /// execution order: whenever a step enters a new call frame, that child node's steps are
+    /// emitted in full before resuming the steps that follow in the parent.
+    ///
+    /// A parent's `trace.steps` only covers the steps it directly executed, so the child node for
+    /// a given call-entering step is the next not-yet-visited entry of `node.children` - call
+    /// frames within one parent are entered in the same order their CALL/CREATE steps execute.
+    fn collect_struct_logs(
+        arena: &CallTraceArena,
+        node_idx: usize,
+        config: GethStructLogConfig,
+        storage_by_contract: &mut HashMap<Address, BTreeMap<String, String>>,
+        out: &mut Vec<GethStructLog>,
+    ) {
+        let node = &arena.nodes()[node_idx];
+        let mut children = node.children.iter();
+
+        for step in &node.trace.steps {
+            out.push(Self::struct_log_for_step(node.trace.address, step, config, storage_by_contract));
+
+            if enters_child_frame(step.op.get()) {
+                if let Some(&child_idx) = children.next() {
+                    Self::collect_struct_logs(arena, child_idx, config, storage_by_contract, out);
+                }
+            }
+        }
+    }
+
+    fn struct_log_for_step(
+        address: Address,
+        step: &CallTraceStep,
+        config: GethStructLogConfig,
+        storage_by_contract: &mut HashMap<Address, BTreeMap<String, String>>,
+    ) -> GethStructLog {
+        let stack = (!config.disable_stack)
+            .then(|| step.stack.as_ref().map(|stack| stack.iter().copied().map(hex_word).collect()))
+            .flatten();
+
+        let memory = (!config.disable_memory)
+            .then(|| {
+                step.memory.as_ref().map(|memory| {
+                    memory.as_bytes().chunks(32).map(hex::encode).collect::<Vec<_>>()
+                })
+            })
+            .flatten();
+
+        let storage = if config.disable_storage {
+            None
+        } else {
+            let contract_storage = storage_by_contract.entry(address).or_default();
+            if let Some(StorageChange { key, value, .. }) = &step.storage_change {
+                contract_storage.insert(hex_word(*key), hex_word(*value));
+            }
+            Some(contract_storage.clone())
+        };
+
+        GethStructLog {
+            pc: step.pc as u64,
+            op: step.op.as_str(),
+            gas: step.gas_remaining,
+            gas_cost: step.gas_cost,
+            depth: step.depth,
+            stack,
+            memory,
+            storage,
+        }
+    }
+}
@@ -0,0 +1,10 @@
+//! Tracing inspectors for revm.
+
+mod fourbyte;
+pub use fourbyte::{FourByteInspector, Selector};
+
+mod geth;
+pub use geth::{GethStructLog, GethStructLogConfig, GethStructLoggerTrace};
+
+mod opcount;
+pub use opcount::{OpcodeCountInspector, OpcodeProfileInspector};
@@ -2,8 +2,9 @@
 //!
 //! See also <https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers>
 
-use revm::{interpreter::Interpreter, EvmWiring, EvmContext};
+use revm::{interpreter::opcode::OpCode, interpreter::Interpreter, EvmContext, EvmWiring};
 use revm_inspector::Inspector;
+use std::collections::BTreeMap;
 
 /// An inspector that counts all opcodes.
 #[derive(Clone, Copy, Debug, Default)]
@@ -28,3 +29,83 @@ where
         self.count += 1;
     }
 }
+
+/// An inspector that builds a per-opcode histogram of execution counts and gas usage.
+///
+/// This mirrors geth's built-in opcode-distribution tracer: every opcode byte seen in [`step`]
+/// is tallied, and the gas spent executing it (the delta between the gas remaining at [`step`]
+/// and at [`step_end`]) is accumulated separately. This is useful for hotspot analysis and
+/// per-opcode gas attribution.
+///
+/// [`step`]: Inspector::step
+/// [`step_end`]: Inspector::step_end
+#[derive(Clone, Copy, Debug)]
+pub struct OpcodeProfileInspector {
+    /// Number of times each opcode byte was executed, indexed by opcode byte.
+    counts: [u64; 256],
+    /// Total gas spent executing each opcode byte, indexed by opcode byte.
+    gas_by_opcode: [u64; 256],
+    /// Opcode byte and gas remaining captured in `step`, consumed again in `step_end`.
+    ///
+    /// `step_end` can't just re-read `interp.current_opcode()`: by the time it runs, the
+    /// interpreter has already advanced `instruction_pointer` past the opcode that was executed
+    /// (and for `PUSHn` that pointer lands inside the immediate data), so re-reading it would
+    /// attribute the gas to the *next* instruction instead.
+    current_step: Option<(u8, u64)>,
+}
+
+impl Default for OpcodeProfileInspector {
+    fn default() -> Self {
+        Self { counts: [0; 256], gas_by_opcode: [0; 256], current_step: None }
+    }
+}
+
+impl OpcodeProfileInspector {
+    /// Returns the total number of opcodes executed, i.e. the sum of all per-opcode counts.
+    #[inline]
+    pub fn count(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Returns the per-opcode execution counts, skipping opcodes that were never executed.
+    ///
+    /// Executed bytes that do not decode to a known [`OpCode`] are left out as well, so they are
+    /// included in [`count`](Self::count) but not in this map.
+    pub fn counts(&self) -> BTreeMap<OpCode, u64> {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count > 0)
+            .filter_map(|(op, &count)| OpCode::new(op as u8).map(|op| (op, count)))
+            .collect()
+    }
+
+    /// Returns the total gas spent per opcode, skipping opcodes that were never executed.
+    ///
+    /// Executed bytes that do not decode to a known [`OpCode`] are left out as well.
+    pub fn gas_by_opcode(&self) -> BTreeMap<OpCode, u64> {
+        self.gas_by_opcode
+            .iter()
+            .enumerate()
+            .filter(|(_, &gas)| gas > 0)
+            .filter_map(|(op, &gas)| OpCode::new(op as u8).map(|op| (op, gas)))
+            .collect()
+    }
+}
+
+impl<EvmWiringT> Inspector<EvmWiringT> for OpcodeProfileInspector
+where
+    EvmWiringT: EvmWiring,
+{
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<EvmWiringT>) {
+        let op = interp.current_opcode();
+        self.counts[op as usize] += 1;
+        self.current_step = Some((op, interp.gas().remaining()));
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<EvmWiringT>) {
+        let Some((op, step_start_gas)) = self.current_step.take() else { return };
+        let gas_used = step_start_gas.saturating_sub(interp.gas().remaining());
+        self.gas_by_opcode[op as usize] += gas_used;
+    }
+}
@@ -0,0 +1,44 @@
+//! Exercises inspector hooks against a database that errors mid-execution, the realistic
+//! condition behind an RPC `debug_traceTransaction` served from a remote/archive backend.
+
+use crate::utils::{inspect_with_fault, FaultKind, TestEvm};
+use alloy_primitives::{Address, Bytes};
+use revm::wiring::{default::TransactTo, result::EVMError};
+use revm_inspectors::tracing::{TracingInspector, TracingInspectorConfig};
+
+#[test]
+fn propagates_account_fault_without_panicking() {
+    let evm = TestEvm::new();
+    let tracer = TracingInspector::new(TracingInspectorConfig::default_geth());
+
+    let err = inspect_with_fault(&evm, tracer, FaultKind::Account, 1)
+        .expect_err("first account lookup should fail");
+    assert!(matches!(err, EVMError::Database(_)));
+}
+
+#[test]
+fn propagates_storage_fault_mid_execution() {
+    // Runtime: PUSH1 1 PUSH1 0 SSTORE PUSH1 0 SLOAD STOP - actually touches storage, unlike a
+    // codeless account, so the fault fires while the interpreter is mid-trace rather than never.
+    let runtime: [u8; 9] = [0x60, 0x01, 0x60, 0x00, 0x55, 0x60, 0x00, 0x54, 0x00];
+
+    // Minimal init code that copies `runtime` into memory and returns it as the deployed code:
+    // PUSH9 <runtime> PUSH1 0 MSTORE PUSH1 9 PUSH1 23 RETURN
+    let mut init_code = vec![0x68];
+    init_code.extend_from_slice(&runtime);
+    init_code.extend_from_slice(&[0x60, 0x00, 0x52, 0x60, 0x09, 0x60, 0x17, 0xf3]);
+
+    let mut evm = TestEvm::new();
+    evm.env.tx.caller = Address::with_last_byte(1);
+    let address = evm.simple_deploy(Bytes::from(init_code));
+
+    evm.env.tx.transact_to = TransactTo::Call(address);
+    evm.env.tx.data = Bytes::default();
+
+    let tracer = TracingInspector::new(TracingInspectorConfig::default_geth());
+    // The SSTORE's refund accounting reads the slot's current value before writing it, so the
+    // first `storage` lookup happens during that instruction.
+    let err = inspect_with_fault(&evm, tracer, FaultKind::Storage, 1)
+        .expect_err("the SSTORE's storage lookup should trigger the injected fault");
+    assert!(matches!(err, EVMError::Database(_)));
+}
@@ -0,0 +1,3 @@
+mod fault_injection;
+mod statetest;
+mod utils;
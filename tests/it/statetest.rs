@@ -0,0 +1,372 @@
+//! Differential runner for the Ethereum Execution-Spec / `GeneralStateTests` JSON fixtures.
+//!
+//! This loads a fixture the same way parity-evm's `EvmTestClient` did: the `pre` account map is
+//! inserted into a [`CacheDB`], the `env`/`transaction` sections are turned into revm's
+//! `BlockEnv`/`TxEnv` and the fork name into a `SpecId` (the `CfgEnv` is left at its default and
+//! is not read from the fixture), and the transaction at each `(data, gas, value)` index named by a
+//! fork's `post` entry is executed - without committing - through [`TestEvm`] with a
+//! [`TracingInspector`] attached. The resulting state root and logs hash are compared against the
+//! fixture's expectation so traces produced here can be diffed against other clients' reference
+//! output to find the first divergent step.
+
+use crate::utils::TestEvm;
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use alloy_trie::TrieAccount;
+use revm::{
+    primitives::KECCAK_EMPTY,
+    specification::hardfork::SpecId,
+    state::AccountInfo,
+    wiring::default::{TransactTo, TxEnv},
+};
+use revm_inspectors::tracing::{TracingInspector, TracingInspectorConfig};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// A single `GeneralStateTests` fixture file, keyed by test name.
+pub type StateTestSuite = BTreeMap<String, StateTest>;
+
+/// One named state test, covering a single transaction executed against every listed fork.
+#[derive(Debug, Deserialize)]
+pub struct StateTest {
+    pub env: StateTestEnv,
+    pub pre: BTreeMap<Address, PreAccount>,
+    pub transaction: MultiTxTransaction,
+    /// Expected post-state per fork name, one entry per `(data, gas, value)` index combination.
+    pub post: BTreeMap<String, Vec<PostStateExpectation>>,
+}
+
+/// The `env` section of a fixture: the block the transaction is executed against.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateTestEnv {
+    pub current_coinbase: Address,
+    pub current_gas_limit: U256,
+    pub current_number: U256,
+    pub current_timestamp: U256,
+    #[serde(default)]
+    pub current_base_fee: Option<U256>,
+}
+
+/// A pre-state account entry.
+#[derive(Debug, Deserialize)]
+pub struct PreAccount {
+    pub balance: U256,
+    pub nonce: U256,
+    pub code: Bytes,
+    pub storage: BTreeMap<U256, U256>,
+}
+
+/// The `transaction` section: shared fields plus the indexed `data`/`gasLimit`/`value` arrays
+/// that `post[fork][i].indexes` points into.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiTxTransaction {
+    pub sender: Address,
+    pub to: Option<Address>,
+    /// Legacy gas price; present pre-London and absent on EIP-1559 fixtures.
+    #[serde(default)]
+    pub gas_price: Option<U256>,
+    /// EIP-1559 `maxFeePerGas`; present from London onward instead of `gasPrice`.
+    #[serde(default)]
+    pub max_fee_per_gas: Option<U256>,
+    /// EIP-1559 `maxPriorityFeePerGas`; present from London onward instead of `gasPrice`.
+    #[serde(default)]
+    pub max_priority_fee_per_gas: Option<U256>,
+    pub nonce: U256,
+    pub data: Vec<Bytes>,
+    pub gas_limit: Vec<U256>,
+    pub value: Vec<U256>,
+}
+
+/// One expected post-state entry for a given fork.
+#[derive(Debug, Deserialize)]
+pub struct PostStateExpectation {
+    pub hash: B256,
+    pub logs: B256,
+    pub indexes: TxIndexes,
+}
+
+/// Indexes into [`MultiTxTransaction`]'s `data`/`gasLimit`/`value` arrays selecting the exact
+/// transaction variant this expectation applies to.
+#[derive(Debug, Deserialize)]
+pub struct TxIndexes {
+    pub data: usize,
+    pub gas: usize,
+    pub value: usize,
+}
+
+/// The outcome of running a single `(fork, data-index, gas-index, value-index)` combination.
+#[derive(Debug)]
+pub struct StateTestCaseResult {
+    pub fork: String,
+    pub indexes: TxIndexes,
+    pub expected_hash: B256,
+    pub computed_hash: B256,
+    pub expected_logs: B256,
+    pub computed_logs: B256,
+    /// The recorded trace, absent when the transaction was rejected before execution (see
+    /// `error`).
+    pub tracer: Option<TracingInspector>,
+    /// The error the indexed transaction was rejected with, if validation or execution failed
+    /// before producing a result. `GeneralStateTests` fixtures commonly include
+    /// invalid-nonce/intrinsic-gas/insufficient-balance cases that are expected to reject the
+    /// transaction and leave the pre-state untouched, rather than execute it.
+    pub error: Option<String>,
+}
+
+impl StateTestCaseResult {
+    /// Whether the computed state root and logs hash matched the fixture's expectation.
+    pub fn passed(&self) -> bool {
+        self.expected_hash == self.computed_hash && self.expected_logs == self.computed_logs
+    }
+}
+
+/// Maps a fixture fork name (e.g. `"Cancun"`, `"Shanghai"`) to the corresponding [`SpecId`].
+///
+/// Unrecognized fork names fall back to [`SpecId::LATEST`] so newer fixtures don't hard-fail the
+/// loader; callers that care should check the fork name themselves.
+fn spec_id_for_fork(fork: &str) -> SpecId {
+    match fork {
+        "Frontier" => SpecId::FRONTIER,
+        "Homestead" => SpecId::HOMESTEAD,
+        "EIP150" => SpecId::TANGERINE,
+        "EIP158" => SpecId::SPURIOUS_DRAGON,
+        "Byzantium" => SpecId::BYZANTIUM,
+        "Constantinople" => SpecId::CONSTANTINOPLE,
+        // The execution-spec-tests fixtures call the Constantinople-with-the-reentrancy-fix fork
+        // "ConstantinopleFix"; revm folds that into Petersburg.
+        "ConstantinopleFix" | "Petersburg" => SpecId::PETERSBURG,
+        "Istanbul" => SpecId::ISTANBUL,
+        "MuirGlacier" => SpecId::MUIR_GLACIER,
+        "Berlin" => SpecId::BERLIN,
+        "London" => SpecId::LONDON,
+        "ArrowGlacier" => SpecId::ARROW_GLACIER,
+        "GrayGlacier" => SpecId::GRAY_GLACIER,
+        // "Paris" is the execution-spec-tests name for the fork the EL fixtures still call
+        // "Merge".
+        "Merge" | "Paris" => SpecId::MERGE,
+        "Shanghai" => SpecId::SHANGHAI,
+        "Cancun" => SpecId::CANCUN,
+        "Prague" => SpecId::PRAGUE,
+        _ => SpecId::LATEST,
+    }
+}
+
+/// Loads the `pre` account map of a [`StateTest`] into the given [`TestEvm`]'s database.
+fn load_pre_state(evm: &mut TestEvm, pre: &BTreeMap<Address, PreAccount>) {
+    for (address, account) in pre {
+        let info = AccountInfo {
+            balance: account.balance,
+            nonce: account.nonce.to::<u64>(),
+            code_hash: keccak256(&account.code),
+            code: Some(revm::bytecode::Bytecode::new_raw(account.code.clone())),
+        };
+        evm.db.insert_account_info(*address, info);
+        for (slot, value) in &account.storage {
+            evm.db
+                .insert_account_storage(*address, *slot, *value)
+                .expect("inserting pre-state storage into an in-memory db cannot fail");
+        }
+    }
+}
+
+/// Executes every `(fork, index)` combination named in a [`StateTest`]'s `post` section against a
+/// fresh [`TestEvm`] seeded with its `pre` state, returning one [`StateTestCaseResult`] per
+/// combination.
+pub fn run_state_test(test: &StateTest) -> Vec<StateTestCaseResult> {
+    let mut results = Vec::new();
+
+    for (fork, expectations) in &test.post {
+        for expectation in expectations {
+            let mut evm = TestEvm::new();
+            load_pre_state(&mut evm, &test.pre);
+
+            evm.spec_id = spec_id_for_fork(fork);
+            evm.env.block.coinbase = test.env.current_coinbase;
+            evm.env.block.gas_limit = test.env.current_gas_limit;
+            evm.env.block.number = test.env.current_number;
+            evm.env.block.timestamp = test.env.current_timestamp;
+            if let Some(base_fee) = test.env.current_base_fee {
+                evm.env.block.basefee = base_fee;
+            }
+
+            // Fixtures from London onward carry `maxFeePerGas`/`maxPriorityFeePerGas` instead of
+            // a legacy `gasPrice`.
+            let (gas_price, gas_priority_fee) =
+                match (test.transaction.gas_price, test.transaction.max_fee_per_gas) {
+                    (Some(gas_price), _) => (gas_price, None),
+                    (None, Some(max_fee_per_gas)) => {
+                        (max_fee_per_gas, test.transaction.max_priority_fee_per_gas)
+                    }
+                    (None, None) => (U256::ZERO, None),
+                };
+
+            evm.env.tx = TxEnv {
+                caller: test.transaction.sender,
+                transact_to: test
+                    .transaction
+                    .to
+                    .map(TransactTo::Call)
+                    .unwrap_or(TransactTo::Create),
+                data: test.transaction.data[expectation.indexes.data].clone(),
+                gas_limit: test.transaction.gas_limit[expectation.indexes.gas].to::<u64>(),
+                gas_price,
+                gas_priority_fee,
+                value: test.transaction.value[expectation.indexes.value],
+                nonce: Some(test.transaction.nonce.to::<u64>()),
+                ..Default::default()
+            };
+
+            let inspector = TracingInspector::new(TracingInspectorConfig::default_geth());
+            // Some fixtures (invalid nonce, insufficient balance, sub-intrinsic gas limit, ...)
+            // expect the indexed transaction to be rejected outright, leaving the pre-state
+            // untouched rather than producing an `ExecutionResult`; treat that as a case outcome
+            // to compare against the expectation instead of panicking the whole run.
+            let (computed_hash, computed_logs, tracer, error) = match evm.inspect(inspector) {
+                Ok((result_and_state, _env, tracer)) => (
+                    state_root_of(&evm, &result_and_state.state),
+                    logs_hash_of(&result_and_state.result),
+                    Some(tracer),
+                    None,
+                ),
+                Err(err) => (
+                    state_root_of(&evm, &revm::wiring::EvmState::default()),
+                    empty_logs_hash(),
+                    None,
+                    Some(err.to_string()),
+                ),
+            };
+
+            results.push(StateTestCaseResult {
+                fork: fork.clone(),
+                indexes: TxIndexes {
+                    data: expectation.indexes.data,
+                    gas: expectation.indexes.gas,
+                    value: expectation.indexes.value,
+                },
+                expected_hash: expectation.hash,
+                computed_hash,
+                expected_logs: expectation.logs,
+                computed_logs,
+                tracer,
+                error,
+            });
+        }
+    }
+
+    results
+}
+
+/// Computes the consensus state root: a secure (keccak-keyed) trie over every account in the
+/// full post-state - not just the ones the transaction touched - each with its own secure storage
+/// trie, the same quantity `post[fork][i].hash` asserts against.
+fn state_root_of(evm: &TestEvm, state: &revm::wiring::EvmState) -> B256 {
+    // Start from every account already known to the pre-state database, then overlay the
+    // accounts the transaction actually touched, so untouched accounts still contribute to the
+    // root instead of being dropped.
+    let mut accounts: BTreeMap<Address, (AccountInfo, BTreeMap<U256, U256>)> = evm
+        .db
+        .accounts
+        .iter()
+        .map(|(address, db_account)| {
+            let storage = db_account.storage.iter().map(|(slot, value)| (*slot, *value)).collect();
+            (*address, (db_account.info.clone(), storage))
+        })
+        .collect();
+
+    for (address, account) in state {
+        if account.is_selfdestructed() {
+            accounts.remove(address);
+            continue;
+        }
+
+        let (info, storage) = accounts.entry(*address).or_default();
+        *info = account.info.clone();
+        for (slot, value) in &account.storage {
+            if value.present_value.is_zero() {
+                storage.remove(slot);
+            } else {
+                storage.insert(*slot, value.present_value);
+            }
+        }
+    }
+
+    let hashed_accounts = accounts
+        .into_iter()
+        // EIP-161: accounts with no balance, no nonce and no code are pruned from the trie,
+        // unless they still hold non-zero storage.
+        .filter(|(_, (info, storage))| {
+            !info.balance.is_zero()
+                || info.nonce != 0
+                || info.code_hash != KECCAK_EMPTY
+                || !storage.is_empty()
+        })
+        .map(|(address, (info, storage))| {
+            let trie_account = TrieAccount {
+                nonce: info.nonce,
+                balance: info.balance,
+                storage_root: storage_root_of(&storage),
+                code_hash: info.code_hash,
+            };
+            (keccak256(address), trie_account)
+        });
+
+    alloy_trie::root::state_root(hashed_accounts)
+}
+
+/// Computes the secure (keccak-keyed) storage trie root for a single account's storage map.
+fn storage_root_of(storage: &BTreeMap<U256, U256>) -> B256 {
+    let hashed_slots = storage
+        .iter()
+        .filter(|(_, value)| !value.is_zero())
+        .map(|(slot, value)| (keccak256(slot.to_be_bytes::<32>()), *value));
+    alloy_trie::root::storage_root(hashed_slots)
+}
+
+/// Computes the RLP hash of the receipt's log list, the same quantity `post[fork][i].logs`
+/// asserts against.
+fn logs_hash_of(result: &revm::wiring::result::ExecutionResult<revm::wiring::result::HaltReason>) -> B256 {
+    keccak256(alloy_rlp::encode(result.logs()))
+}
+
+/// Computes the logs hash of a rejected transaction: the RLP encoding of an empty log list
+/// (`0xc0`), since a transaction that was never executed emits no logs.
+fn empty_logs_hash() -> B256 {
+    keccak256([0xc0u8])
+}
+
+#[test]
+fn runs_general_state_test_fixtures() {
+    let Ok(dir) = std::env::var("STATE_TEST_FIXTURES_DIR") else {
+        // No fixture directory configured; this differential runner is exercised against the
+        // official consensus test suite out-of-band, not as part of the default test run.
+        return;
+    };
+
+    for entry in std::fs::read_dir(dir).expect("failed to read fixtures dir") {
+        let path = entry.expect("failed to read fixture entry").path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+
+        let content = std::fs::read_to_string(&path).expect("failed to read fixture file");
+        let suite: StateTestSuite =
+            serde_json::from_str(&content).expect("failed to parse fixture file");
+
+        for (name, test) in &suite {
+            for case in run_state_test(test) {
+                assert!(
+                    case.passed(),
+                    "{name} ({}, indexes {:?}) diverged: expected state root {}, got {}; expected logs hash {}, got {}; error: {:?}",
+                    case.fork,
+                    case.indexes,
+                    case.expected_hash,
+                    case.computed_hash,
+                    case.expected_logs,
+                    case.computed_logs,
+                    case.error,
+                );
+            }
+        }
+    }
+}
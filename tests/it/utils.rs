@@ -1,8 +1,9 @@
-use alloy_primitives::{Address, Bytes, U256};
+use alloy_primitives::{Address, Bytes, B256, U256};
 use colorchoice::ColorChoice;
 use revm::{
     database_interface::EmptyDB,
     specification::hardfork::SpecId,
+    state::{AccountInfo, Bytecode},
     wiring::{
         default::{block::BlockEnv, CfgEnv, Env, TransactTo, TxEnv},
         result::{EVMError, ExecutionResult, HaltReason, InvalidTransaction, ResultAndState},
@@ -16,7 +17,7 @@ use revm_inspectors::tracing::{
     TraceWriter, TraceWriterConfig, TracingInspector, TracingInspectorConfig,
 };
 use revm_wiring::{EvmWiring, TransactionValidation};
-use std::{convert::Infallible, fmt::Debug};
+use std::{convert::Infallible, fmt, fmt::Debug};
 
 type TestDb = CacheDB<EmptyDB>;
 pub type TestWiring<'a, InspectorT> = EthereumWiring<&'a mut TestDb, InspectorT>;
@@ -92,7 +93,7 @@ impl TestEvm {
         self.env.tx.data = data;
         self.env.tx.transact_to = TransactTo::Create;
 
-        let (ResultAndState::<HaltReason> { result, state }, env) =
+        let (ResultAndState::<HaltReason> { result, state }, env, _inspector) =
             self.inspect::<InspectorT>(inspector)?;
         self.db.commit(state);
         self.env = env;
@@ -116,7 +117,7 @@ impl TestEvm {
     {
         self.env.tx.data = data;
         self.env.tx.transact_to = TransactTo::Call(address);
-        let (ResultAndState { result, state }, env) = self.inspect(inspector)?;
+        let (ResultAndState { result, state }, env, _inspector) = self.inspect(inspector)?;
         self.db.commit(state);
         self.env = env;
         Ok(result)
@@ -126,7 +127,7 @@ impl TestEvm {
         &mut self,
         inspector: InspectorT,
     ) -> Result<
-        (ResultAndState<HaltReason>, Box<Env<BlockEnv, TxEnv>>),
+        (ResultAndState<HaltReason>, Box<Env<BlockEnv, TxEnv>>, InspectorT),
         EVMError<Infallible, InvalidTransaction>,
     >
     where
@@ -142,13 +143,22 @@ impl TestEvm {
 }
 
 /// Executes the [EnvWithHandlerCfg] against the given [Database] without committing state changes.
+///
+/// Returns the inspector that actually ran alongside the result, since
+/// `Evm::into_db_and_env_with_handler_cfg` drops the external context - callers that need to
+/// inspect what was recorded (e.g. a [`TracingInspector`]'s trace arena) can't get it back any
+/// other way.
 pub fn inspect<'a, EvmWiringT>(
     db: EvmWiringT::Database,
     env: Box<Env<<EvmWiringT as EvmWiring>::Block, <EvmWiringT as EvmWiring>::Transaction>>,
     spec_id: EvmWiringT::Hardfork,
     inspector: EvmWiringT::ExternalContext,
 ) -> Result<
-    (ResultAndState<EvmWiringT::HaltReason>, Box<Env<EvmWiringT::Block, EvmWiringT::Transaction>>),
+    (
+        ResultAndState<EvmWiringT::HaltReason>,
+        Box<Env<EvmWiringT::Block, EvmWiringT::Transaction>>,
+        EvmWiringT::ExternalContext,
+    ),
     EVMError<
         <<EvmWiringT as EvmWiring>::Database as Database>::Error,
         <<EvmWiringT as EvmWiring>::Transaction as TransactionValidation>::ValidationError,
@@ -170,8 +180,8 @@ where
         .append_handler_register(inspector_handle_register)
         .build();
     let res = evm.transact()?;
-    let (_, env, _) = evm.into_db_and_env_with_handler_cfg();
-    Ok((res, env))
+    let (context, _handler_cfg) = evm.into_context_with_handler_cfg();
+    Ok((res, context.evm.env, context.external))
 }
 
 pub fn write_traces(tracer: &TracingInspector) -> String {
@@ -198,3 +208,129 @@ pub fn deploy_contract(code: Bytes, deployer: Address, spec_id: SpecId) -> (Addr
 
     (evm.simple_deploy(code), evm)
 }
+
+/// Which [`Database`] lookup a [`FaultyDb`] should fail on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FaultKind {
+    /// `Database::basic`, i.e. an account info lookup.
+    Account,
+    /// `Database::storage`.
+    Storage,
+    /// `Database::code_by_hash`.
+    Code,
+    /// `Database::block_hash`.
+    BlockHash,
+}
+
+/// Error returned by [`FaultyDb`], either an injected fault or a passthrough from the wrapped
+/// database.
+#[derive(Debug)]
+pub enum FaultyDbError<E> {
+    /// The configured fault fired on this call.
+    Injected(FaultKind),
+    /// The wrapped database returned an error on its own.
+    Inner(E),
+}
+
+impl<E: fmt::Display> fmt::Display for FaultyDbError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Injected(kind) => write!(f, "injected fault on {kind:?} lookup"),
+            Self::Inner(err) => err.fmt(f),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for FaultyDbError<E> {}
+
+/// A [`Database`] wrapper that fails the Nth call to a configured lookup kind with
+/// [`FaultyDbError::Injected`], to exercise inspectors against a backend that can error mid
+/// execution - the realistic condition behind an RPC `debug_traceTransaction` served from a
+/// remote/archive backend.
+#[derive(Debug)]
+pub struct FaultyDb<DB> {
+    inner: DB,
+    kind: FaultKind,
+    /// 1-based call number on which the fault fires; `0` disables the fault.
+    fail_on_call: usize,
+    calls: usize,
+}
+
+impl<DB> FaultyDb<DB> {
+    /// Wraps `inner`, failing the `fail_on_call`'th call of kind `kind` (1-based).
+    pub fn new(inner: DB, kind: FaultKind, fail_on_call: usize) -> Self {
+        Self { inner, kind, fail_on_call, calls: 0 }
+    }
+
+    /// Returns `Err` if this call is the configured fault, bumping the internal call counter as a
+    /// side effect.
+    fn maybe_fail(&mut self, kind: FaultKind) -> Result<(), FaultyDbError<DB::Error>>
+    where
+        DB: Database,
+    {
+        if kind != self.kind {
+            return Ok(());
+        }
+        self.calls += 1;
+        if self.calls == self.fail_on_call {
+            return Err(FaultyDbError::Injected(kind));
+        }
+        Ok(())
+    }
+}
+
+impl<DB> Database for FaultyDb<DB>
+where
+    DB: Database,
+{
+    type Error = FaultyDbError<DB::Error>;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.maybe_fail(FaultKind::Account)?;
+        self.inner.basic(address).map_err(FaultyDbError::Inner)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.maybe_fail(FaultKind::Code)?;
+        self.inner.code_by_hash(code_hash).map_err(FaultyDbError::Inner)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.maybe_fail(FaultKind::Storage)?;
+        self.inner.storage(address, index).map_err(FaultyDbError::Inner)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.maybe_fail(FaultKind::BlockHash)?;
+        self.inner.block_hash(number).map_err(FaultyDbError::Inner)
+    }
+}
+
+/// Runs `inspector` against a clone of `evm`'s database wrapped in a [`FaultyDb`] that fails the
+/// `fail_on_call`'th lookup of `kind`, returning whatever error (if any) propagated out of
+/// [`inspect`].
+///
+/// This is the fallible counterpart to [`TestEvm::inspect`]: it threads the wrapped database's
+/// real `Error` type through [`EVMError::Database`] instead of collapsing it to [`Infallible`],
+/// so callers can assert that inspector hooks handle a mid-execution database error without
+/// panicking or losing trace frames.
+pub fn inspect_with_fault<InspectorT>(
+    evm: &TestEvm,
+    inspector: InspectorT,
+    kind: FaultKind,
+    fail_on_call: usize,
+) -> Result<
+    (ResultAndState<HaltReason>, Box<Env<BlockEnv, TxEnv>>, InspectorT),
+    EVMError<FaultyDbError<Infallible>, InvalidTransaction>,
+>
+where
+    InspectorT: for<'a> Inspector<EthereumWiring<&'a mut FaultyDb<TestDb>, InspectorT>> + Debug,
+{
+    let mut db = FaultyDb::new(evm.db.clone(), kind, fail_on_call);
+    inspect::<EthereumWiring<&mut FaultyDb<TestDb>, InspectorT>>(
+        &mut db,
+        evm.env.clone(),
+        evm.spec_id,
+        inspector,
+    )
+}